@@ -1,7 +1,7 @@
-use crate::point_cloud::PointCloud;
+use crate::point_cloud::{PointCloud, VoxelMode};
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
-use tauri::State;
+use tauri::{State, Window};
 
 pub struct AppState {
     pub cloud: Mutex<Option<PointCloud>>,
@@ -15,17 +15,56 @@ pub struct ProcessingStatus {
     pub point_count: usize,
 }
 
+/// Payload emitted on the `processing-progress` event as each stage of
+/// `process_point_cloud` advances, so the frontend doesn't have to poll
+/// `get_processing_status` to show a live progress bar.
+#[derive(Clone, Serialize)]
+pub struct ProgressEvent {
+    pub stage: String,
+    pub progress: f32,
+    pub point_count: usize,
+}
+
+/// Update the shared status and emit a `processing-progress` event carrying
+/// the same data, so polling clients and event-driven clients agree.
+fn report_progress(
+    window: &Window,
+    state: &State<'_, AppState>,
+    stage: &str,
+    progress: f32,
+    point_count: usize,
+) {
+    {
+        let mut status = state.status.lock().unwrap();
+        status.stage = stage.to_string();
+        status.progress = progress;
+        status.point_count = point_count;
+    }
+    let _ = window.emit(
+        "processing-progress",
+        ProgressEvent {
+            stage: stage.to_string(),
+            progress,
+            point_count,
+        },
+    );
+}
+
 #[derive(Serialize)]
 pub struct LoadResult {
     pub point_count: usize,
     pub has_colors: bool,
+    pub has_normals: bool,
     pub bounds: [[f32; 3]; 2],
 }
 
 #[tauri::command]
-pub async fn load_point_cloud(path: String, state: State<'_, AppState>) -> Result<LoadResult, String> {
+pub async fn load_point_cloud(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<LoadResult, String> {
     let cloud = PointCloud::from_ply(std::path::Path::new(&path))?;
-    
+
     let bounds = if cloud.points.is_empty() {
         [[0.0; 3], [0.0; 3]]
     } else {
@@ -39,13 +78,14 @@ pub async fn load_point_cloud(path: String, state: State<'_, AppState>) -> Resul
         }
         [min, max]
     };
-    
+
     let result = LoadResult {
         point_count: cloud.points.len(),
         has_colors: !cloud.colors.is_empty(),
+        has_normals: !cloud.normals.is_empty(),
         bounds,
     };
-    
+
     *state.cloud.lock().unwrap() = Some(cloud);
     Ok(result)
 }
@@ -53,39 +93,71 @@ pub async fn load_point_cloud(path: String, state: State<'_, AppState>) -> Resul
 #[derive(Deserialize)]
 pub struct ProcessOptions {
     pub voxel_size: Option<f32>,
+    #[serde(default)]
+    pub voxel_mode: VoxelMode,
     pub remove_outliers: bool,
     pub outlier_k: Option<usize>,
     pub outlier_std: Option<f32>,
+    pub estimate_normals: Option<usize>,
 }
 
 #[tauri::command]
-pub async fn process_point_cloud(options: ProcessOptions, state: State<'_, AppState>) -> Result<usize, String> {
+pub async fn process_point_cloud(
+    options: ProcessOptions,
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
     let mut cloud_guard = state.cloud.lock().unwrap();
     let cloud = cloud_guard.as_mut().ok_or("No point cloud loaded")?;
-    
-    {
-        let mut status = state.status.lock().unwrap();
-        status.stage = "Processing".into();
-        status.progress = 0.0;
-    }
-    
+
+    report_progress(&window, &state, "Processing", 0.0, cloud.points.len());
+
     if let Some(voxel_size) = options.voxel_size {
-        cloud.voxel_downsample(voxel_size);
+        let total = cloud.points.len();
+        let mut on_progress = |done: usize, _total: usize| {
+            report_progress(
+                &window,
+                &state,
+                "Downsampling",
+                done as f32 / total as f32,
+                done,
+            );
+        };
+        cloud.voxel_downsample(voxel_size, options.voxel_mode, Some(&mut on_progress));
     }
-    
+
     if options.remove_outliers {
         let k = options.outlier_k.unwrap_or(20);
         let std = options.outlier_std.unwrap_or(2.0);
-        cloud.remove_outliers(k, std);
+        let total = cloud.points.len();
+        let mut on_progress = |done: usize, _total: usize| {
+            report_progress(
+                &window,
+                &state,
+                "Removing outliers",
+                done as f32 / total as f32,
+                done,
+            );
+        };
+        cloud.remove_outliers(k, std, Some(&mut on_progress));
     }
-    
-    {
-        let mut status = state.status.lock().unwrap();
-        status.stage = "Complete".into();
-        status.progress = 1.0;
-        status.point_count = cloud.points.len();
+
+    if let Some(k) = options.estimate_normals {
+        let total = cloud.points.len();
+        let mut on_progress = |done: usize, _total: usize| {
+            report_progress(
+                &window,
+                &state,
+                "Estimating normals",
+                done as f32 / total as f32,
+                done,
+            );
+        };
+        cloud.estimate_normals(k, None, Some(&mut on_progress));
     }
-    
+
+    report_progress(&window, &state, "Complete", 1.0, cloud.points.len());
+
     Ok(cloud.points.len())
 }
 
@@ -93,3 +165,14 @@ pub async fn process_point_cloud(options: ProcessOptions, state: State<'_, AppSt
 pub fn get_processing_status(state: State<'_, AppState>) -> ProcessingStatus {
     state.status.lock().unwrap().clone()
 }
+
+#[tauri::command]
+pub async fn save_point_cloud(
+    path: String,
+    binary: bool,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let cloud_guard = state.cloud.lock().unwrap();
+    let cloud = cloud_guard.as_ref().ok_or("No point cloud loaded")?;
+    cloud.to_ply(std::path::Path::new(&path), binary)
+}
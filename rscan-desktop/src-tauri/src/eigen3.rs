@@ -0,0 +1,119 @@
+//! Closed-form eigen-decomposition for symmetric 3x3 matrices. Used to find
+//! the least-variance direction (the surface normal) of a point
+//! neighborhood's covariance matrix without an iterative solver.
+
+/// Return a unit eigenvector of `m`'s smallest eigenvalue, using the
+/// analytic formula for symmetric 3x3 matrices (no iteration required).
+pub fn smallest_eigenvector(m: [[f64; 3]; 3]) -> [f64; 3] {
+    let off_diag_sq = m[0][1] * m[0][1] + m[0][2] * m[0][2] + m[1][2] * m[1][2];
+    if off_diag_sq < 1e-18 {
+        // Already diagonal: the eigenvectors are the coordinate axes.
+        return if m[0][0] <= m[1][1] && m[0][0] <= m[2][2] {
+            [1.0, 0.0, 0.0]
+        } else if m[1][1] <= m[2][2] {
+            [0.0, 1.0, 0.0]
+        } else {
+            [0.0, 0.0, 1.0]
+        };
+    }
+
+    let trace_over_3 = (m[0][0] + m[1][1] + m[2][2]) / 3.0;
+    let p2 = (m[0][0] - trace_over_3).powi(2)
+        + (m[1][1] - trace_over_3).powi(2)
+        + (m[2][2] - trace_over_3).powi(2)
+        + 2.0 * off_diag_sq;
+    let p = (p2 / 6.0).sqrt();
+
+    let mut b = [[0.0; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            b[i][j] = (m[i][j] - if i == j { trace_over_3 } else { 0.0 }) / p;
+        }
+    }
+    let r = (det3(&b) / 2.0).clamp(-1.0, 1.0);
+    let phi = r.acos() / 3.0;
+
+    // Eigenvalues in descending order: eig1 >= eig2 >= eig3.
+    let eig1 = trace_over_3 + 2.0 * p * phi.cos();
+    let eig3 = trace_over_3 + 2.0 * p * (phi + 2.0 * std::f64::consts::PI / 3.0).cos();
+    let eig2 = 3.0 * trace_over_3 - eig1 - eig3;
+
+    let smallest = eig1.min(eig2).min(eig3);
+    eigenvector_for(&m, smallest)
+}
+
+fn det3(m: &[[f64; 3]; 3]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+/// Solve `(m - lambda * I) v = 0` for symmetric `m`. Any two independent
+/// rows of the (rank-deficient) shifted matrix span the orthogonal
+/// complement of its null space, so their cross product is the eigenvector;
+/// take whichever pair of rows gives the largest cross product to avoid
+/// near-parallel rows producing a near-zero result. When `lambda` is a
+/// repeated eigenvalue the shifted matrix has rank 1 and every cross product
+/// vanishes; any unit vector orthogonal to its non-zero row is returned then.
+fn eigenvector_for(m: &[[f64; 3]; 3], lambda: f64) -> [f64; 3] {
+    let a = [
+        [m[0][0] - lambda, m[0][1], m[0][2]],
+        [m[1][0], m[1][1] - lambda, m[1][2]],
+        [m[2][0], m[2][1], m[2][2] - lambda],
+    ];
+
+    let largest = |x: &[f64; 3], y: &[f64; 3]| norm_sq(*x).total_cmp(&norm_sq(*y));
+    let best = [cross(a[0], a[1]), cross(a[0], a[2]), cross(a[1], a[2])]
+        .into_iter()
+        .max_by(largest)
+        .unwrap();
+    let row = a.into_iter().max_by(largest).unwrap();
+    if norm_sq(best) > 1e-12 * norm_sq(row).powi(2) {
+        return normalize(best);
+    }
+    let mut axis = [0.0; 3];
+    axis[(0..3).min_by(|&i, &j| row[i].abs().total_cmp(&row[j].abs())).unwrap()] = 1.0;
+    normalize(cross(row, axis))
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn norm_sq(v: [f64; 3]) -> f64 {
+    v[0] * v[0] + v[1] * v[1] + v[2] * v[2]
+}
+
+fn normalize(v: [f64; 3]) -> [f64; 3] {
+    let n = norm_sq(v).sqrt();
+    if n < 1e-12 {
+        return [0.0, 0.0, 1.0];
+    }
+    [v[0] / n, v[1] / n, v[2] / n]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_xy_covariance_yields_z_normal() {
+        // A point cloud spread over the x/y plane has zero variance along z,
+        // so the smallest-eigenvalue eigenvector must be +/- the z axis.
+        let cov = [[4.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.0]];
+        let normal = smallest_eigenvector(cov);
+        assert!((normal[2].abs() - 1.0).abs() < 1e-6);
+        assert!(normal[0].abs() < 1e-6);
+        assert!(normal[1].abs() < 1e-6);
+    }
+
+    #[test]
+    fn does_not_panic_on_nan_covariance() {
+        let cov = [[f64::NAN, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 2.0]];
+        let _ = smallest_eigenvector(cov);
+    }
+}
@@ -0,0 +1,231 @@
+//! A simple 3D KD-tree over point indices, built by recursively splitting on
+//! the axis of largest spread. Used by both outlier removal and normal
+//! estimation; each of them builds its own tree over the current points.
+
+use std::collections::BinaryHeap;
+
+/// Number of point indices stored at a leaf before it is split further.
+const LEAF_SIZE: usize = 16;
+
+enum Node {
+    Leaf {
+        indices: Vec<usize>,
+    },
+    Split {
+        axis: usize,
+        value: f32,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+}
+
+pub struct KdTree {
+    root: Node,
+}
+
+impl KdTree {
+    /// Build a KD-tree over `points`. The tree borrows nothing; it stores
+    /// indices into `points` and must be queried against the same slice.
+    pub fn build(points: &[[f32; 3]]) -> Self {
+        let indices: Vec<usize> = (0..points.len()).collect();
+        Self {
+            root: Self::build_node(points, indices),
+        }
+    }
+
+    fn build_node(points: &[[f32; 3]], mut indices: Vec<usize>) -> Node {
+        if indices.len() <= LEAF_SIZE {
+            return Node::Leaf { indices };
+        }
+
+        let axis = Self::widest_axis(points, &indices);
+        let mid = indices.len() / 2;
+        indices.select_nth_unstable_by(mid, |&a, &b| points[a][axis].total_cmp(&points[b][axis]));
+        let value = points[indices[mid]][axis];
+
+        let right_indices = indices.split_off(mid);
+        let left_indices = indices;
+
+        Node::Split {
+            axis,
+            value,
+            left: Box::new(Self::build_node(points, left_indices)),
+            right: Box::new(Self::build_node(points, right_indices)),
+        }
+    }
+
+    fn widest_axis(points: &[[f32; 3]], indices: &[usize]) -> usize {
+        let mut min = points[indices[0]];
+        let mut max = points[indices[0]];
+        for &i in indices {
+            let p = points[i];
+            for axis in 0..3 {
+                min[axis] = min[axis].min(p[axis]);
+                max[axis] = max[axis].max(p[axis]);
+            }
+        }
+        let spread = [max[0] - min[0], max[1] - min[1], max[2] - min[2]];
+        if spread[0] >= spread[1] && spread[0] >= spread[2] {
+            0
+        } else if spread[1] >= spread[2] {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Find the `k` nearest neighbors of `points[query]`, excluding `query`
+    /// itself. Returns `(index, squared_distance)` pairs sorted by ascending
+    /// distance.
+    pub fn k_nearest(&self, points: &[[f32; 3]], query: usize, k: usize) -> Vec<(usize, f32)> {
+        let target = points[query];
+        let mut heap: BinaryHeap<Neighbor> = BinaryHeap::with_capacity(k + 1);
+        Self::search_node(&self.root, points, target, query, k, &mut heap);
+
+        let mut result: Vec<(usize, f32)> = heap.into_iter().map(|n| (n.index, n.dist)).collect();
+        result.sort_by(|a, b| a.1.total_cmp(&b.1));
+        result
+    }
+
+    fn search_node(
+        node: &Node,
+        points: &[[f32; 3]],
+        target: [f32; 3],
+        exclude: usize,
+        k: usize,
+        heap: &mut BinaryHeap<Neighbor>,
+    ) {
+        match node {
+            Node::Leaf { indices } => {
+                for &i in indices {
+                    if i == exclude {
+                        continue;
+                    }
+                    let dist = squared_distance(points[i], target);
+                    push_candidate(heap, k, i, dist);
+                }
+            }
+            Node::Split {
+                axis,
+                value,
+                left,
+                right,
+            } => {
+                let diff = target[*axis] - value;
+                let (near, far) = if diff <= 0.0 {
+                    (left, right)
+                } else {
+                    (right, left)
+                };
+                Self::search_node(near, points, target, exclude, k, heap);
+
+                let worst = heap.peek().map(|n| n.dist);
+                if heap.len() < k || worst.map_or(true, |w| diff * diff < w) {
+                    Self::search_node(far, points, target, exclude, k, heap);
+                }
+            }
+        }
+    }
+}
+
+struct Neighbor {
+    index: usize,
+    dist: f32,
+}
+
+impl PartialEq for Neighbor {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist == other.dist
+    }
+}
+impl Eq for Neighbor {}
+impl PartialOrd for Neighbor {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Neighbor {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.dist.total_cmp(&other.dist)
+    }
+}
+
+fn push_candidate(heap: &mut BinaryHeap<Neighbor>, k: usize, index: usize, dist: f32) {
+    if heap.len() < k {
+        heap.push(Neighbor { index, dist });
+    } else if let Some(worst) = heap.peek() {
+        if dist < worst.dist {
+            heap.pop();
+            heap.push(Neighbor { index, dist });
+        }
+    }
+}
+
+fn squared_distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force_k_nearest(points: &[[f32; 3]], query: usize, k: usize) -> Vec<(usize, f32)> {
+        let mut dists: Vec<(usize, f32)> = points
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != query)
+            .map(|(i, p)| (i, squared_distance(*p, points[query])))
+            .collect();
+        dists.sort_by(|a, b| a.1.total_cmp(&b.1));
+        dists.truncate(k);
+        dists
+    }
+
+    fn random_points(n: usize) -> Vec<[f32; 3]> {
+        // A small deterministic LCG so the test doesn't depend on `rand`.
+        let mut state: u64 = 0x2545F4914F6CDD1D;
+        let mut next = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((state >> 33) as i64 % 1000) as f32 / 10.0
+        };
+        (0..n).map(|_| [next(), next(), next()]).collect()
+    }
+
+    #[test]
+    fn k_nearest_matches_brute_force() {
+        let points = random_points(200);
+        let tree = KdTree::build(&points);
+
+        for query in [0, 1, 50, 100, 199] {
+            let expected = brute_force_k_nearest(&points, query, 5);
+            let actual = tree.k_nearest(&points, query, 5);
+            assert_eq!(actual.len(), expected.len());
+            for ((actual_idx, actual_dist), (_, expected_dist)) in actual.iter().zip(&expected) {
+                assert!(
+                    (actual_dist - expected_dist).abs() < 1e-4,
+                    "query {query}: neighbor {actual_idx} got dist {actual_dist}, expected {expected_dist}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn k_nearest_excludes_query_point() {
+        let points = vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [2.0, 0.0, 0.0]];
+        let tree = KdTree::build(&points);
+        let neighbors = tree.k_nearest(&points, 0, 2);
+        assert!(neighbors.iter().all(|&(i, _)| i != 0));
+    }
+
+    #[test]
+    fn k_nearest_handles_nan_coordinates_without_panicking() {
+        let points = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [f32::NAN, 1.0, 0.0],
+            [2.0, 0.0, 0.0],
+        ];
+        let tree = KdTree::build(&points);
+        let _ = tree.k_nearest(&points, 0, 2);
+    }
+}
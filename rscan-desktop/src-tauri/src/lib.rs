@@ -1,5 +1,8 @@
-mod point_cloud;
 mod commands;
+mod eigen3;
+mod kdtree;
+mod ply;
+mod point_cloud;
 
 use commands::AppState;
 use std::sync::Mutex;
@@ -17,6 +20,7 @@ pub fn run() {
             commands::load_point_cloud,
             commands::process_point_cloud,
             commands::get_processing_status,
+            commands::save_point_cloud,
         ])
         .run(tauri::generate_context!())
         .expect("error running tauri application");
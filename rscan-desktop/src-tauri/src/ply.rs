@@ -0,0 +1,214 @@
+//! Minimal PLY (Polygon File Format) header parsing shared by the point
+//! cloud reader and writer. Only the `vertex` element is understood, which
+//! is all `PointCloud` needs.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PropertyType {
+    Float,
+    Double,
+    Char,
+    UChar,
+    Short,
+    UShort,
+    Int,
+    UInt,
+}
+
+impl PropertyType {
+    fn from_ply_name(name: &str) -> Result<Self, String> {
+        match name {
+            "float" | "float32" => Ok(Self::Float),
+            "double" | "float64" => Ok(Self::Double),
+            "char" | "int8" => Ok(Self::Char),
+            "uchar" | "uint8" => Ok(Self::UChar),
+            "short" | "int16" => Ok(Self::Short),
+            "ushort" | "uint16" => Ok(Self::UShort),
+            "int" | "int32" => Ok(Self::Int),
+            "uint" | "uint32" => Ok(Self::UInt),
+            other => Err(format!("unsupported PLY property type '{other}'")),
+        }
+    }
+
+    pub fn byte_size(&self) -> usize {
+        match self {
+            Self::Char | Self::UChar => 1,
+            Self::Short | Self::UShort => 2,
+            Self::Float | Self::Int | Self::UInt => 4,
+            Self::Double => 8,
+        }
+    }
+
+    /// The name this type is written back out as in a PLY header.
+    fn to_ply_name(self) -> &'static str {
+        match self {
+            Self::Float => "float",
+            Self::Double => "double",
+            Self::Char => "char",
+            Self::UChar => "uchar",
+            Self::Short => "short",
+            Self::UShort => "ushort",
+            Self::Int => "int",
+            Self::UInt => "uint",
+        }
+    }
+
+    /// Encode `value` as this property type in little-endian byte order,
+    /// appending to `out`. The inverse of `decode`.
+    pub fn encode_le(&self, value: f64, out: &mut Vec<u8>) {
+        match self {
+            Self::Float => out.extend_from_slice(&(value as f32).to_le_bytes()),
+            Self::Double => out.extend_from_slice(&value.to_le_bytes()),
+            Self::Char => out.extend_from_slice(&(value as i8).to_le_bytes()),
+            Self::UChar => out.extend_from_slice(&(value as u8).to_le_bytes()),
+            Self::Short => out.extend_from_slice(&(value as i16).to_le_bytes()),
+            Self::UShort => out.extend_from_slice(&(value as u16).to_le_bytes()),
+            Self::Int => out.extend_from_slice(&(value as i32).to_le_bytes()),
+            Self::UInt => out.extend_from_slice(&(value as u32).to_le_bytes()),
+        }
+    }
+
+    /// Decode `bytes` (exactly `byte_size()` long) as an `f64` so callers can
+    /// treat every numeric PLY type uniformly.
+    pub fn decode(&self, bytes: &[u8], little_endian: bool) -> f64 {
+        macro_rules! read {
+            ($ty:ty) => {{
+                let mut buf = [0u8; std::mem::size_of::<$ty>()];
+                buf.copy_from_slice(bytes);
+                (if little_endian {
+                    <$ty>::from_le_bytes(buf)
+                } else {
+                    <$ty>::from_be_bytes(buf)
+                }) as f64
+            }};
+        }
+        match self {
+            Self::Float => read!(f32),
+            Self::Double => read!(f64),
+            Self::Char => read!(i8),
+            Self::UChar => read!(u8),
+            Self::Short => read!(i16),
+            Self::UShort => read!(u16),
+            Self::Int => read!(i32),
+            Self::UInt => read!(u32),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Property {
+    pub name: String,
+    pub ty: PropertyType,
+}
+
+pub struct Header {
+    pub format: Format,
+    pub vertex_count: usize,
+    pub properties: Vec<Property>,
+}
+
+impl Header {
+    pub fn property_index(&self, name: &str) -> Option<usize> {
+        self.properties.iter().position(|p| p.name == name)
+    }
+
+    pub fn record_size(&self) -> usize {
+        self.properties.iter().map(|p| p.ty.byte_size()).sum()
+    }
+}
+
+/// Parse the PLY header from `reader`, leaving the reader positioned right
+/// after the `end_header` line (i.e. at the start of vertex data).
+pub fn parse_header<R: std::io::BufRead>(reader: &mut R) -> Result<Header, String> {
+    let mut line = String::new();
+    let mut format = None;
+    let mut vertex_count = None;
+    let mut properties = Vec::new();
+    let mut in_vertex_element = false;
+
+    reader.read_line(&mut line).map_err(|e| e.to_string())?;
+    if line.trim() != "ply" {
+        return Err("not a PLY file: missing 'ply' magic line".to_string());
+    }
+
+    loop {
+        line.clear();
+        let bytes_read = reader.read_line(&mut line).map_err(|e| e.to_string())?;
+        if bytes_read == 0 {
+            return Err("unexpected end of file while reading PLY header".to_string());
+        }
+        let trimmed = line.trim();
+        let tokens: Vec<&str> = trimmed.split_whitespace().collect();
+
+        match tokens.as_slice() {
+            ["format", kind, _version] => {
+                format = Some(match *kind {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    other => return Err(format!("unsupported PLY format '{other}'")),
+                });
+            }
+            ["element", "vertex", count] => {
+                vertex_count = Some(
+                    count
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid vertex count '{count}'"))?,
+                );
+                in_vertex_element = true;
+            }
+            ["element", ..] => {
+                // Any other element (e.g. `face`) ends the vertex property
+                // list; we don't support reading faces.
+                in_vertex_element = false;
+            }
+            ["property", "list", ..] => {
+                // List properties (e.g. face indices) are skipped; they
+                // never appear on the vertex element we care about.
+            }
+            ["property", ty, name] if in_vertex_element => {
+                properties.push(Property {
+                    name: name.to_string(),
+                    ty: PropertyType::from_ply_name(ty)?,
+                });
+            }
+            ["end_header"] => break,
+            _ => {}
+        }
+    }
+
+    Ok(Header {
+        format: format.ok_or("PLY header missing 'format' line")?,
+        vertex_count: vertex_count.ok_or("PLY header missing vertex element")?,
+        properties,
+    })
+}
+
+/// Write a PLY header declaring exactly `properties` on a `vertex` element
+/// with `vertex_count` rows, in the given `format`.
+pub fn write_header<W: std::io::Write>(
+    writer: &mut W,
+    format: Format,
+    vertex_count: usize,
+    properties: &[Property],
+) -> std::io::Result<()> {
+    writeln!(writer, "ply")?;
+    let format_name = match format {
+        Format::Ascii => "ascii",
+        Format::BinaryLittleEndian => "binary_little_endian",
+        Format::BinaryBigEndian => "binary_big_endian",
+    };
+    writeln!(writer, "format {format_name} 1.0")?;
+    writeln!(writer, "element vertex {vertex_count}")?;
+    for prop in properties {
+        writeln!(writer, "property {} {}", prop.ty.to_ply_name(), prop.name)?;
+    }
+    writeln!(writer, "end_header")?;
+    Ok(())
+}
@@ -1,6 +1,33 @@
+use crate::eigen3;
+use crate::kdtree::KdTree;
+use crate::ply::{self, Format};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// How `PointCloud::voxel_downsample` picks the representative point for
+/// each occupied voxel.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum VoxelMode {
+    /// Keep the first point encountered in the voxel (scan order).
+    First,
+    /// Average every point (and color/normal) in the voxel.
+    Centroid,
+}
+
+impl Default for VoxelMode {
+    fn default() -> Self {
+        Self::Centroid
+    }
+}
+
+fn voxel_key(p: [f32; 3], voxel_size: f32) -> (i32, i32, i32) {
+    (
+        (p[0] / voxel_size).floor() as i32,
+        (p[1] / voxel_size).floor() as i32,
+        (p[2] / voxel_size).floor() as i32,
+    )
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PointCloud {
     pub points: Vec<[f32; 3]>,
@@ -18,105 +45,451 @@ impl PointCloud {
     }
 
     pub fn from_ply(path: &std::path::Path) -> Result<Self, String> {
-        use std::io::{BufRead, BufReader};
+        use std::io::BufReader;
         let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
         let mut reader = BufReader::new(file);
-        let mut line = String::new();
-        let mut vertex_count = 0;
-        let mut has_color = false;
-
-        // Parse header
-        loop {
-            line.clear();
-            reader.read_line(&mut line).map_err(|e| e.to_string())?;
-            if line.starts_with("element vertex") {
-                vertex_count = line.split_whitespace().nth(2).unwrap().parse().unwrap();
+        let header = ply::parse_header(&mut reader)?;
+
+        let x = header.property_index("x");
+        let y = header.property_index("y");
+        let z = header.property_index("z");
+        let (x, y, z) = match (x, y, z) {
+            (Some(x), Some(y), Some(z)) => (x, y, z),
+            _ => return Err("PLY vertex element is missing x/y/z properties".to_string()),
+        };
+        let red = header.property_index("red");
+        let green = header.property_index("green");
+        let blue = header.property_index("blue");
+        let nx = header.property_index("nx");
+        let ny = header.property_index("ny");
+        let nz = header.property_index("nz");
+        let has_color = red.is_some() && green.is_some() && blue.is_some();
+        let has_normals = nx.is_some() && ny.is_some() && nz.is_some();
+
+        let mut cloud = PointCloud::new();
+        match header.format {
+            Format::Ascii => {
+                use std::io::BufRead;
+                let mut line = String::new();
+                for row in 0..header.vertex_count {
+                    line.clear();
+                    let bytes_read = reader.read_line(&mut line).map_err(|e| e.to_string())?;
+                    if bytes_read == 0 {
+                        return Err(format!(
+                            "PLY file ended after {row} of {} vertices",
+                            header.vertex_count
+                        ));
+                    }
+                    let vals: Vec<&str> = line.split_whitespace().collect();
+                    if vals.len() < header.properties.len() {
+                        return Err(format!(
+                            "vertex {row} has {} fields, expected {}",
+                            vals.len(),
+                            header.properties.len()
+                        ));
+                    }
+                    let parse = |idx: usize| -> Result<f32, String> {
+                        vals[idx]
+                            .parse::<f32>()
+                            .map_err(|_| format!("vertex {row}: invalid number '{}'", vals[idx]))
+                    };
+
+                    cloud.points.push([parse(x)?, parse(y)?, parse(z)?]);
+                    if has_color {
+                        cloud.colors.push([
+                            parse(red.unwrap())? as u8,
+                            parse(green.unwrap())? as u8,
+                            parse(blue.unwrap())? as u8,
+                        ]);
+                    }
+                    if has_normals {
+                        cloud.normals.push([
+                            parse(nx.unwrap())?,
+                            parse(ny.unwrap())?,
+                            parse(nz.unwrap())?,
+                        ]);
+                    }
+                }
+            }
+            Format::BinaryLittleEndian | Format::BinaryBigEndian => {
+                use std::io::Read;
+                let little_endian = header.format == Format::BinaryLittleEndian;
+                let record_size = header.record_size();
+                let mut offsets = Vec::with_capacity(header.properties.len());
+                let mut offset = 0;
+                for prop in &header.properties {
+                    offsets.push(offset);
+                    offset += prop.ty.byte_size();
+                }
+
+                let mut record = vec![0u8; record_size];
+                for row in 0..header.vertex_count {
+                    reader.read_exact(&mut record).map_err(|e| {
+                        format!(
+                            "PLY file truncated at vertex {row} of {} ({e})",
+                            header.vertex_count
+                        )
+                    })?;
+                    let field = |idx: usize| -> f32 {
+                        let prop = &header.properties[idx];
+                        let start = offsets[idx];
+                        let end = start + prop.ty.byte_size();
+                        prop.ty.decode(&record[start..end], little_endian) as f32
+                    };
+
+                    cloud.points.push([field(x), field(y), field(z)]);
+                    if has_color {
+                        cloud.colors.push([
+                            field(red.unwrap()) as u8,
+                            field(green.unwrap()) as u8,
+                            field(blue.unwrap()) as u8,
+                        ]);
+                    }
+                    if has_normals {
+                        cloud.normals.push([
+                            field(nx.unwrap()),
+                            field(ny.unwrap()),
+                            field(nz.unwrap()),
+                        ]);
+                    }
+                }
             }
-            if line.contains("red") {
-                has_color = true;
+        }
+        Ok(cloud)
+    }
+
+    /// Write the cloud as a PLY file, declaring only the properties that
+    /// are actually populated (`x y z`, plus `red green blue` if there are
+    /// colors, plus `nx ny nz` if there are normals). `binary` selects
+    /// binary-little-endian encoding over ASCII.
+    pub fn to_ply(&self, path: &std::path::Path, binary: bool) -> Result<(), String> {
+        use std::io::{BufWriter, Write};
+
+        let has_color = !self.colors.is_empty();
+        let has_normals = !self.normals.is_empty();
+
+        let mut properties = vec![
+            ply::Property {
+                name: "x".to_string(),
+                ty: ply::PropertyType::Float,
+            },
+            ply::Property {
+                name: "y".to_string(),
+                ty: ply::PropertyType::Float,
+            },
+            ply::Property {
+                name: "z".to_string(),
+                ty: ply::PropertyType::Float,
+            },
+        ];
+        if has_color {
+            for name in ["red", "green", "blue"] {
+                properties.push(ply::Property {
+                    name: name.to_string(),
+                    ty: ply::PropertyType::UChar,
+                });
             }
-            if line.trim() == "end_header" {
-                break;
+        }
+        if has_normals {
+            for name in ["nx", "ny", "nz"] {
+                properties.push(ply::Property {
+                    name: name.to_string(),
+                    ty: ply::PropertyType::Float,
+                });
             }
         }
 
-        let mut cloud = PointCloud::new();
-        for _ in 0..vertex_count {
-            line.clear();
-            reader.read_line(&mut line).map_err(|e| e.to_string())?;
-            let vals: Vec<&str> = line.split_whitespace().collect();
-
-            cloud.points.push([
-                vals[0].parse().unwrap(),
-                vals[1].parse().unwrap(),
-                vals[2].parse().unwrap(),
-            ]);
-
-            if has_color && vals.len() >= 6 {
-                cloud.colors.push([
-                    vals[3].parse().unwrap(),
-                    vals[4].parse().unwrap(),
-                    vals[5].parse().unwrap(),
-                ]);
+        let format = if binary {
+            Format::BinaryLittleEndian
+        } else {
+            Format::Ascii
+        };
+
+        let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+        let mut writer = BufWriter::new(file);
+        ply::write_header(&mut writer, format, self.points.len(), &properties)
+            .map_err(|e| e.to_string())?;
+
+        for i in 0..self.points.len() {
+            let p = self.points[i];
+            if binary {
+                let mut record =
+                    Vec::with_capacity(properties.iter().map(|p| p.ty.byte_size()).sum());
+                for v in p {
+                    ply::PropertyType::Float.encode_le(v as f64, &mut record);
+                }
+                if has_color {
+                    for v in self.colors[i] {
+                        ply::PropertyType::UChar.encode_le(v as f64, &mut record);
+                    }
+                }
+                if has_normals {
+                    for v in self.normals[i] {
+                        ply::PropertyType::Float.encode_le(v as f64, &mut record);
+                    }
+                }
+                writer.write_all(&record).map_err(|e| e.to_string())?;
+            } else {
+                let mut fields: Vec<String> = p.iter().map(|v| v.to_string()).collect();
+                if has_color {
+                    fields.extend(self.colors[i].iter().map(|v| v.to_string()));
+                }
+                if has_normals {
+                    fields.extend(self.normals[i].iter().map(|v| v.to_string()));
+                }
+                writeln!(writer, "{}", fields.join(" ")).map_err(|e| e.to_string())?;
             }
         }
-        Ok(cloud)
+
+        Ok(())
     }
 
-    pub fn voxel_downsample(&mut self, voxel_size: f32) {
-        let mut voxels: HashMap<(i32, i32, i32), (usize, [f32; 3])> = HashMap::new();
+    /// Downsample by bucketing points into voxels of `voxel_size` and
+    /// emitting one point per occupied voxel, per `mode`.
+    pub fn voxel_downsample(
+        &mut self,
+        voxel_size: f32,
+        mode: VoxelMode,
+        mut on_progress: Option<&mut dyn FnMut(usize, usize)>,
+    ) {
+        let has_color = !self.colors.is_empty();
+        let has_normals = !self.normals.is_empty();
+        let total = self.points.len();
 
-        for (i, p) in self.points.iter().enumerate() {
-            let key = (
-                (p[0] / voxel_size).floor() as i32,
-                (p[1] / voxel_size).floor() as i32,
-                (p[2] / voxel_size).floor() as i32,
-            );
-            voxels.entry(key).or_insert((i, *p));
-        }
+        match mode {
+            VoxelMode::First => {
+                let mut voxels: HashMap<(i32, i32, i32), usize> = HashMap::new();
+                for (i, p) in self.points.iter().enumerate() {
+                    voxels.entry(voxel_key(*p, voxel_size)).or_insert(i);
+                    report_progress(&mut on_progress, i + 1, total);
+                }
 
-        let indices: Vec<usize> = voxels.values().map(|(i, _)| *i).collect();
-        self.points = indices.iter().map(|&i| self.points[i]).collect();
-        if !self.colors.is_empty() {
-            self.colors = indices.iter().map(|&i| self.colors[i]).collect();
+                let indices: Vec<usize> = voxels.values().copied().collect();
+                self.points = indices.iter().map(|&i| self.points[i]).collect();
+                if has_color {
+                    self.colors = indices.iter().map(|&i| self.colors[i]).collect();
+                }
+                if has_normals {
+                    self.normals = indices.iter().map(|&i| self.normals[i]).collect();
+                }
+            }
+            VoxelMode::Centroid => {
+                struct Accum {
+                    count: u32,
+                    position: [f32; 3],
+                    color: [u32; 3],
+                    normal: [f32; 3],
+                    first_normal: [f32; 3],
+                }
+
+                let mut voxels: HashMap<(i32, i32, i32), Accum> = HashMap::new();
+                for (i, p) in self.points.iter().enumerate() {
+                    let entry = voxels.entry(voxel_key(*p, voxel_size)).or_insert(Accum {
+                        count: 0,
+                        position: [0.0; 3],
+                        color: [0; 3],
+                        normal: [0.0; 3],
+                        first_normal: [0.0; 3],
+                    });
+                    if has_normals && entry.count == 0 {
+                        entry.first_normal = self.normals[i];
+                    }
+                    entry.count += 1;
+                    for a in 0..3 {
+                        entry.position[a] += p[a];
+                    }
+                    if has_color {
+                        for a in 0..3 {
+                            entry.color[a] += self.colors[i][a] as u32;
+                        }
+                    }
+                    if has_normals {
+                        for a in 0..3 {
+                            entry.normal[a] += self.normals[i][a];
+                        }
+                    }
+                    report_progress(&mut on_progress, i + 1, total);
+                }
+
+                let mut points = Vec::with_capacity(voxels.len());
+                let mut colors = Vec::with_capacity(voxels.len());
+                let mut normals = Vec::with_capacity(voxels.len());
+                for accum in voxels.into_values() {
+                    let n = accum.count as f32;
+                    points.push([
+                        accum.position[0] / n,
+                        accum.position[1] / n,
+                        accum.position[2] / n,
+                    ]);
+                    if has_color {
+                        colors.push([
+                            (accum.color[0] / accum.count) as u8,
+                            (accum.color[1] / accum.count) as u8,
+                            (accum.color[2] / accum.count) as u8,
+                        ]);
+                    }
+                    if has_normals {
+                        let averaged = [
+                            accum.normal[0] / n,
+                            accum.normal[1] / n,
+                            accum.normal[2] / n,
+                        ];
+                        let len = (averaged[0] * averaged[0]
+                            + averaged[1] * averaged[1]
+                            + averaged[2] * averaged[2])
+                            .sqrt();
+                        normals.push(if len < 1e-6 {
+                            // Contributing normals nearly cancelled out (e.g.
+                            // a voxel straddling a sharp edge); fall back to
+                            // the first contributing normal rather than
+                            // emitting a near-zero vector.
+                            accum.first_normal
+                        } else {
+                            [averaged[0] / len, averaged[1] / len, averaged[2] / len]
+                        });
+                    }
+                }
+
+                self.points = points;
+                if has_color {
+                    self.colors = colors;
+                }
+                if has_normals {
+                    self.normals = normals;
+                }
+            }
         }
     }
 
-    pub fn remove_outliers(&mut self, k: usize, std_ratio: f32) {
-        // Simplified: remove points far from mean
-        if self.points.len() < k {
+    /// Statistical outlier removal: for every point, compute its mean
+    /// distance to its `k` nearest neighbors, then drop points whose mean
+    /// neighbor distance exceeds `mean + std_ratio * stddev` over the whole
+    /// cloud. Neighbor queries are backed by a KD-tree so this scales to
+    /// large scans instead of the naive O(n^2) all-pairs approach.
+    pub fn remove_outliers(
+        &mut self,
+        k: usize,
+        std_ratio: f32,
+        mut on_progress: Option<&mut dyn FnMut(usize, usize)>,
+    ) {
+        if self.points.len() <= k {
             return;
         }
 
-        let mean: [f32; 3] = [
-            self.points.iter().map(|p| p[0]).sum::<f32>() / self.points.len() as f32,
-            self.points.iter().map(|p| p[1]).sum::<f32>() / self.points.len() as f32,
-            self.points.iter().map(|p| p[2]).sum::<f32>() / self.points.len() as f32,
-        ];
-
-        let distances: Vec<f32> = self
-            .points
-            .iter()
-            .map(|p| {
-                ((p[0] - mean[0]).powi(2) + (p[1] - mean[1]).powi(2) + (p[2] - mean[2]).powi(2))
-                    .sqrt()
+        let tree = KdTree::build(&self.points);
+        let total = self.points.len();
+        let mean_neighbor_dist: Vec<f32> = (0..total)
+            .map(|i| {
+                let neighbors = tree.k_nearest(&self.points, i, k);
+                let sum: f32 = neighbors.iter().map(|(_, d)| d.sqrt()).sum();
+                let mean = sum / neighbors.len() as f32;
+                report_progress(&mut on_progress, i + 1, total);
+                mean
             })
             .collect();
 
-        let mean_dist = distances.iter().sum::<f32>() / distances.len() as f32;
-        let std = (distances
+        let n = mean_neighbor_dist.len() as f32;
+        let mean = mean_neighbor_dist.iter().sum::<f32>() / n;
+        let variance = mean_neighbor_dist
             .iter()
-            .map(|d| (d - mean_dist).powi(2))
+            .map(|d| (d - mean).powi(2))
             .sum::<f32>()
-            / distances.len() as f32)
-            .sqrt();
-        let threshold = mean_dist + std_ratio * std;
+            / n;
+        let std = variance.sqrt();
+
+        let keep: Vec<bool> = if std == 0.0 {
+            vec![true; mean_neighbor_dist.len()]
+        } else {
+            let threshold = mean + std_ratio * std;
+            mean_neighbor_dist.iter().map(|d| *d <= threshold).collect()
+        };
+
+        self.apply_mask(&keep);
+    }
+
+    /// Estimate a per-point surface normal by PCA over each point's `k`
+    /// nearest neighbors: the normal is the eigenvector of the neighborhood
+    /// covariance matrix with the smallest eigenvalue (the direction the
+    /// neighborhood varies least along). When `viewpoint` is given, each
+    /// normal is flipped to point towards it.
+    pub fn estimate_normals(
+        &mut self,
+        k: usize,
+        viewpoint: Option<[f32; 3]>,
+        mut on_progress: Option<&mut dyn FnMut(usize, usize)>,
+    ) {
+        if self.points.len() <= k {
+            return;
+        }
+
+        let tree = KdTree::build(&self.points);
+        let total = self.points.len();
+        let mut normals = vec![[0.0f32; 3]; total];
+
+        for i in 0..total {
+            let neighbors = tree.k_nearest(&self.points, i, k);
+            let n = neighbors.len() as f64;
+
+            let mut centroid = [0.0f64; 3];
+            for &(j, _) in &neighbors {
+                for a in 0..3 {
+                    centroid[a] += self.points[j][a] as f64;
+                }
+            }
+            for c in &mut centroid {
+                *c /= n;
+            }
+
+            let mut cov = [[0.0f64; 3]; 3];
+            for &(j, _) in &neighbors {
+                let d = [
+                    self.points[j][0] as f64 - centroid[0],
+                    self.points[j][1] as f64 - centroid[1],
+                    self.points[j][2] as f64 - centroid[2],
+                ];
+                for a in 0..3 {
+                    for b in 0..3 {
+                        cov[a][b] += d[a] * d[b];
+                    }
+                }
+            }
+            for row in &mut cov {
+                for v in row {
+                    *v /= n;
+                }
+            }
+
+            let normal = eigen3::smallest_eigenvector(cov);
+            let mut normal = [normal[0] as f32, normal[1] as f32, normal[2] as f32];
+
+            if let Some(viewpoint) = viewpoint {
+                let to_viewpoint = [
+                    viewpoint[0] - self.points[i][0],
+                    viewpoint[1] - self.points[i][1],
+                    viewpoint[2] - self.points[i][2],
+                ];
+                let dot = normal[0] * to_viewpoint[0]
+                    + normal[1] * to_viewpoint[1]
+                    + normal[2] * to_viewpoint[2];
+                if dot < 0.0 {
+                    normal = [-normal[0], -normal[1], -normal[2]];
+                }
+            }
 
-        let mask: Vec<bool> = distances.iter().map(|d| *d < threshold).collect();
+            normals[i] = normal;
+            report_progress(&mut on_progress, i + 1, total);
+        }
+
+        self.normals = normals;
+    }
+
+    /// Keep only the points (and any populated colors/normals) for which
+    /// `mask` is `true`, preserving relative order.
+    fn apply_mask(&mut self, mask: &[bool]) {
         self.points = self
             .points
             .iter()
-            .zip(&mask)
+            .zip(mask)
             .filter(|(_, &m)| m)
             .map(|(p, _)| *p)
             .collect();
@@ -124,10 +497,151 @@ impl PointCloud {
             self.colors = self
                 .colors
                 .iter()
-                .zip(&mask)
+                .zip(mask)
                 .filter(|(_, &m)| m)
                 .map(|(c, _)| *c)
                 .collect();
         }
+        if !self.normals.is_empty() {
+            self.normals = self
+                .normals
+                .iter()
+                .zip(mask)
+                .filter(|(_, &m)| m)
+                .map(|(n, _)| *n)
+                .collect();
+        }
+    }
+}
+
+/// How many processed points must elapse between progress callback
+/// invocations, so large clouds don't pay for a lock/emit on every point.
+const PROGRESS_BATCH: usize = 1000;
+
+fn report_progress(
+    on_progress: &mut Option<&mut dyn FnMut(usize, usize)>,
+    done: usize,
+    total: usize,
+) {
+    if let Some(callback) = on_progress {
+        if done % PROGRESS_BATCH == 0 || done == total {
+            callback(done, total);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_cloud() -> PointCloud {
+        PointCloud {
+            points: vec![[0.0, 0.0, 0.0], [1.0, 2.0, 3.0], [-1.5, 0.5, 4.25]],
+            colors: vec![[255, 0, 0], [0, 255, 0], [0, 0, 255]],
+            normals: vec![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+
+    fn scratch_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rscan-test-{name}"))
+    }
+
+    fn assert_clouds_close(a: &PointCloud, b: &PointCloud) {
+        assert_eq!(a.points.len(), b.points.len());
+        for (p, q) in a.points.iter().zip(&b.points) {
+            for i in 0..3 {
+                assert!((p[i] - q[i]).abs() < 1e-4, "{p:?} != {q:?}");
+            }
+        }
+        assert_eq!(a.colors, b.colors);
+        assert_eq!(a.normals.len(), b.normals.len());
+        for (n, m) in a.normals.iter().zip(&b.normals) {
+            for i in 0..3 {
+                assert!((n[i] - m[i]).abs() < 1e-4, "{n:?} != {m:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn ascii_ply_round_trips() {
+        let path = scratch_path("ascii.ply");
+        let cloud = sample_cloud();
+        cloud.to_ply(&path, false).unwrap();
+        let loaded = PointCloud::from_ply(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_clouds_close(&cloud, &loaded);
+    }
+
+    #[test]
+    fn binary_ply_round_trips() {
+        let path = scratch_path("binary.ply");
+        let cloud = sample_cloud();
+        cloud.to_ply(&path, true).unwrap();
+        let loaded = PointCloud::from_ply(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_clouds_close(&cloud, &loaded);
+    }
+
+    #[test]
+    fn ply_without_color_or_normals_round_trips() {
+        let path = scratch_path("bare.ply");
+        let cloud = PointCloud {
+            points: vec![[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]],
+            colors: vec![],
+            normals: vec![],
+        };
+        cloud.to_ply(&path, true).unwrap();
+        let loaded = PointCloud::from_ply(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_clouds_close(&cloud, &loaded);
+    }
+
+    #[test]
+    fn centroid_voxel_mode_averages_position_color_and_normal() {
+        // First two points share a voxel at size 1.0; the third is alone in
+        // its own voxel far away.
+        let mut cloud = PointCloud {
+            points: vec![[0.1, 0.1, 0.1], [0.2, 0.2, 0.2], [5.5, 5.5, 5.5]],
+            colors: vec![[0, 0, 0], [10, 10, 10], [200, 200, 200]],
+            normals: vec![[0.0, 0.0, 1.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]],
+        };
+
+        cloud.voxel_downsample(1.0, VoxelMode::Centroid, None);
+
+        assert_eq!(cloud.points.len(), 2);
+        let (merged, alone) = if cloud.points[0][0] < 1.0 {
+            (0, 1)
+        } else {
+            (1, 0)
+        };
+
+        for i in 0..3 {
+            assert!((cloud.points[merged][i] - 0.15).abs() < 1e-4);
+        }
+        assert_eq!(cloud.colors[merged], [5, 5, 5]);
+        for i in 0..3 {
+            assert!((cloud.normals[merged][i] - [0.0, 0.0, 1.0][i]).abs() < 1e-4);
+        }
+
+        assert_eq!(cloud.points[alone], [5.5, 5.5, 5.5]);
+        assert_eq!(cloud.colors[alone], [200, 200, 200]);
+        assert_eq!(cloud.normals[alone], [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn centroid_voxel_mode_falls_back_when_normals_cancel() {
+        // Two opposite unit normals in the same voxel average to zero;
+        // the result should fall back to the first contributing normal
+        // instead of emitting a zero-length vector.
+        let mut cloud = PointCloud {
+            points: vec![[0.1, 0.0, 0.0], [0.2, 0.0, 0.0]],
+            colors: vec![],
+            normals: vec![[0.0, 0.0, 1.0], [0.0, 0.0, -1.0]],
+        };
+
+        cloud.voxel_downsample(1.0, VoxelMode::Centroid, None);
+
+        assert_eq!(cloud.normals.len(), 1);
+        assert_eq!(cloud.normals[0], [0.0, 0.0, 1.0]);
     }
 }